@@ -1,10 +1,15 @@
 
+use crate::LookTransform;
+use freefly::FreeFlyTransform;
+
 use bevy::{
     prelude::*,
     ecs::schedule::ShouldRun
 };
+use serde::{Deserialize, Serialize};
 
-pub mod fps;
+pub mod fps_3d;
+pub mod freefly;
 pub mod orbit;
 pub mod unreal;
 
@@ -14,6 +19,69 @@ pub enum InputBehavior {
     Disable,
 }
 
+/// A single rebindable input, used by a controller's `*ControlBindings` component so that
+/// a logical action (e.g. "pan") can be driven by either a keyboard key or a mouse button.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum Binding {
+    Key(KeyCode),
+    MouseButton(MouseButton),
+}
+
+impl Binding {
+    pub fn pressed(&self, keyboard: &Input<KeyCode>, mouse_buttons: &Input<MouseButton>) -> bool {
+        match self {
+            Binding::Key(key) => keyboard.pressed(*key),
+            Binding::MouseButton(button) => mouse_buttons.pressed(*button),
+        }
+    }
+}
+
+/// Marks the camera entity that currently receives input.
+pub struct ActiveLookCamera;
+
+/// The key that cycles the active camera. Defaults to `C`.
+pub struct CycleCameraBinding(pub KeyCode);
+
+impl Default for CycleCameraBinding {
+    fn default() -> Self {
+        Self(KeyCode::C)
+    }
+}
+
+/// Cycles `ActiveLookCamera` forward through every entity with a `LookTransform` or a
+/// `FreeFlyTransform`, wrapping around. Not added by any plugin automatically; add it (and
+/// the `CycleCameraBinding` resource, if not using the default) to your `App` to enable
+/// camera switching.
+pub fn cycle_active_camera_system(
+    cycle_key: Option<Res<CycleCameraBinding>>,
+    keyboard: Res<Input<KeyCode>>,
+    mut commands: Commands,
+    cameras: Query<Entity, Or<(With<LookTransform>, With<FreeFlyTransform>)>>,
+    active: Query<Entity, With<ActiveLookCamera>>,
+) {
+    let cycle_key = cycle_key.map(|b| b.0).unwrap_or(KeyCode::C);
+    if !keyboard.just_pressed(cycle_key) {
+        return;
+    }
+
+    let mut entities: Vec<Entity> = cameras.iter().collect();
+    if entities.is_empty() {
+        return;
+    }
+    entities.sort();
+
+    let current = active.iter().next();
+    let next_index = match current.and_then(|e| entities.iter().position(|&c| c == e)) {
+        Some(index) => (index + 1) % entities.len(),
+        None => 0,
+    };
+
+    if let Some(current) = current {
+        commands.entity(current).remove::<ActiveLookCamera>();
+    }
+    commands.entity(entities[next_index]).insert(ActiveLookCamera);
+}
+
 fn set_default_input_behavior(
     mut command: Commands,
 ) {
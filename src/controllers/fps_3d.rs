@@ -1,4 +1,6 @@
 use crate::{LookAngles, LookTransform, LookTransformBundle, Smoother};
+use crate::look_transform64::{LookAngles64, LookTransform64};
+use super::{set_default_input_behavior, should_consume_input, ActiveLookCamera, InputBehavior};
 
 use bevy::{
     app::prelude::*,
@@ -7,18 +9,23 @@ use bevy::{
     math::prelude::*,
     render::prelude::*,
     transform::components::Transform,
+    window::prelude::*,
 };
 use serde::{Deserialize, Serialize};
 
 #[derive(Default)]
 pub struct Fps3dCameraPlugin {
     pub override_input_system: bool,
+    /// While enabled, locks and hides the OS cursor whenever an `Fps3dCameraController` is
+    /// enabled, releasing it when disabled or on `Escape`.
+    pub grab_cursor: bool,
 }
 
 impl Fps3dCameraPlugin {
     pub fn new(override_input_system: bool) -> Self {
         Self {
             override_input_system,
+            ..Default::default()
         }
     }
 }
@@ -26,10 +33,19 @@ impl Fps3dCameraPlugin {
 impl Plugin for Fps3dCameraPlugin {
     fn build(&self, app: &mut AppBuilder) {
         let app = app
+            .add_startup_system(set_default_input_behavior.system())
             .add_system(control_system.system())
             .add_event::<ControlEvent>();
         if !self.override_input_system {
-            app.add_system(default_input_map.system());
+            app.add_system_set(
+                SystemSet::new()
+                    .with_run_criteria(should_consume_input.system())
+                    .with_system(default_input_map.system()),
+            );
+        }
+        if self.grab_cursor {
+            app.add_startup_system(set_default_cursor_released_state.system())
+                .add_system(grab_cursor_system.system());
         }
     }
 }
@@ -37,6 +53,7 @@ impl Plugin for Fps3dCameraPlugin {
 #[derive(Bundle)]
 pub struct Fps3dCameraBundle {
     controller: Fps3dCameraController,
+    bindings: Fps3dControlBindings,
     #[bundle]
     look_transform: LookTransformBundle,
     #[bundle]
@@ -55,6 +72,7 @@ impl Fps3dCameraBundle {
 
         Self {
             controller,
+            bindings: Fps3dControlBindings::default(),
             look_transform: LookTransformBundle {
                 transform: LookTransform { eye, target },
                 smoother: Smoother::new(controller.smoothing_weight),
@@ -62,6 +80,11 @@ impl Fps3dCameraBundle {
             perspective,
         }
     }
+
+    pub fn with_bindings(mut self, bindings: Fps3dControlBindings) -> Self {
+        self.bindings = bindings;
+        self
+    }
 }
 
 /// Your typical first-person camera controller.
@@ -71,6 +94,10 @@ pub struct Fps3dCameraController {
     pub mouse_rotate_sensitivity: Vec2,
     pub translate_sensitivity: f32,
     pub smoothing_weight: f32,
+    /// Multiplies `translate_sensitivity` while `key_run` is held.
+    pub sprint_speed_factor: f32,
+    /// Multiplies `translate_sensitivity` while `key_slow` is held.
+    pub slow_speed_factor: f32,
 }
 
 impl Default for Fps3dCameraController {
@@ -80,6 +107,38 @@ impl Default for Fps3dCameraController {
             mouse_rotate_sensitivity: Vec2::splat(0.002),
             translate_sensitivity: 0.5,
             smoothing_weight: 0.9,
+            sprint_speed_factor: 2.0,
+            slow_speed_factor: 0.3,
+        }
+    }
+}
+
+/// Rebindable keys for `Fps3dCameraController`'s actions.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct Fps3dControlBindings {
+    pub key_forward: KeyCode,
+    pub key_back: KeyCode,
+    pub key_left: KeyCode,
+    pub key_right: KeyCode,
+    pub key_up: KeyCode,
+    pub key_down: KeyCode,
+    /// Held to multiply translation speed by `sprint_speed_factor`.
+    pub key_run: KeyCode,
+    /// Held to multiply translation speed by `slow_speed_factor`.
+    pub key_slow: KeyCode,
+}
+
+impl Default for Fps3dControlBindings {
+    fn default() -> Self {
+        Self {
+            key_forward: KeyCode::W,
+            key_back: KeyCode::S,
+            key_left: KeyCode::A,
+            key_right: KeyCode::D,
+            key_up: KeyCode::E,
+            key_down: KeyCode::Q,
+            key_run: KeyCode::LShift,
+            key_slow: KeyCode::LAlt,
         }
     }
 }
@@ -93,11 +152,18 @@ pub fn default_input_map(
     mut events: EventWriter<ControlEvent>,
     keyboard: Res<Input<KeyCode>>,
     mut mouse_motion_events: EventReader<MouseMotion>,
-    cameras: Query<(&Fps3dCameraController, &LookTransform)>,
+    active_cameras: Query<
+        (&Fps3dCameraController, &Fps3dControlBindings, &LookTransform),
+        With<ActiveLookCamera>,
+    >,
+    all_cameras: Query<(&Fps3dCameraController, &Fps3dControlBindings, &LookTransform)>,
 ) {
-    // Can only control one camera at a time.
-    let (controller, transform) = if let Some((controller, transform)) = cameras.iter().next() {
-        (controller, transform)
+    // Prefer the camera marked active; if none is marked (e.g. a single-camera scene),
+    // fall back to the first one found.
+    let (controller, bindings, transform) = if let Some(c) = active_cameras.iter().next() {
+        c
+    } else if let Some(c) = all_cameras.iter().next() {
+        c
     } else {
         return;
     };
@@ -106,6 +172,8 @@ pub fn default_input_map(
         enabled,
         translate_sensitivity,
         mouse_rotate_sensitivity,
+        sprint_speed_factor,
+        slow_speed_factor,
         ..
     } = *controller;
 
@@ -122,13 +190,24 @@ pub fn default_input_map(
         mouse_rotate_sensitivity * cursor_delta,
     ));
 
+    let speed_factor = if keyboard.pressed(bindings.key_run) {
+        sprint_speed_factor
+    } else if keyboard.pressed(bindings.key_slow) {
+        slow_speed_factor
+    } else {
+        1.0
+    };
+    let translate_sensitivity = translate_sensitivity * speed_factor;
+
     let look_vector = transform.look_direction();
 
     for (key, dir) in [
-        (KeyCode::W, look_vector),
-        (KeyCode::A, -look_vector.cross(Vec3::Y)),
-        (KeyCode::S, -look_vector),
-        (KeyCode::D, look_vector.cross(Vec3::Y)),
+        (bindings.key_forward, look_vector),
+        (bindings.key_left, -look_vector.cross(Vec3::Y)),
+        (bindings.key_back, -look_vector),
+        (bindings.key_right, look_vector.cross(Vec3::Y)),
+        (bindings.key_up, Vec3::Y),
+        (bindings.key_down, -Vec3::Y),
     ]
     .iter()
     .cloned()
@@ -141,15 +220,17 @@ pub fn default_input_map(
 
 pub fn control_system(
     mut events: EventReader<ControlEvent>,
+    active: Query<Entity, (With<Fps3dCameraController>, With<ActiveLookCamera>)>,
+    any: Query<Entity, With<Fps3dCameraController>>,
     mut cameras: Query<(&Fps3dCameraController, &mut LookTransform)>,
 ) {
-    // Can only control one camera at a time.
-    let (controller, mut transform) =
-        if let Some((controller, transform)) = cameras.iter_mut().next() {
-            (controller, transform)
-        } else {
-            return;
-        };
+    // Prefer the camera marked active; if none is marked (e.g. a single-camera scene),
+    // fall back to the first one found.
+    let target = active.iter().next().or_else(|| any.iter().next());
+    let (controller, mut transform) = match target.and_then(|e| cameras.get_mut(e).ok()) {
+        Some(c) => c,
+        None => return,
+    };
 
     if controller.enabled {
         let look_vector = transform.look_direction();
@@ -176,3 +257,86 @@ pub fn control_system(
         events.iter(); // Drop the events.
     }
 }
+
+/// Double-precision counterpart to `control_system`, operating on `LookTransform64` for
+/// scenes that rebase around a `FloatingOrigin` instead of trusting an f32 `Transform`.
+pub fn control_system_f64(
+    mut events: EventReader<ControlEvent>,
+    active: Query<Entity, (With<Fps3dCameraController>, With<ActiveLookCamera>)>,
+    any: Query<Entity, With<Fps3dCameraController>>,
+    mut cameras: Query<(&Fps3dCameraController, &mut LookTransform64)>,
+) {
+    // Prefer the camera marked active; if none is marked (e.g. a single-camera scene),
+    // fall back to the first one found.
+    let target = active.iter().next().or_else(|| any.iter().next());
+    let (controller, mut transform) = match target.and_then(|e| cameras.get_mut(e).ok()) {
+        Some(c) => c,
+        None => return,
+    };
+
+    if controller.enabled {
+        let look_vector = transform.look_direction();
+        let mut look_angles = LookAngles64::from_vector(look_vector);
+
+        for event in events.iter() {
+            match event {
+                ControlEvent::Rotate(delta) => {
+                    // Rotates with pitch and yaw.
+                    look_angles.add_yaw(-delta.x as f64);
+                    look_angles.add_pitch(-delta.y as f64);
+                }
+                ControlEvent::TranslateEye(delta) => {
+                    // Translates up/down (Y) left/right (X) and forward/back (Z).
+                    transform.eye += delta.as_dvec3();
+                }
+            }
+        }
+
+        look_angles.assert_not_looking_up();
+
+        transform.target = transform.eye + transform.radius() * look_angles.unit_vector();
+    } else {
+        events.iter(); // Drop the events.
+    }
+}
+
+/// Set by `grab_cursor_system` when the player presses `Escape`, so the cursor stays released
+/// on later frames instead of being immediately re-grabbed. Cleared by a left click, which
+/// lets the cursor be grabbed again.
+#[derive(Default)]
+pub struct CursorReleasedByUser(bool);
+
+fn set_default_cursor_released_state(mut commands: Commands) {
+    commands.insert_resource(CursorReleasedByUser::default());
+}
+
+/// Locks and hides the primary window's cursor while any `Fps3dCameraController` is enabled,
+/// and releases it again when none are, the player presses `Escape` (until they click back
+/// in), or `InputBehavior` is set to `Disable` - runs unconditionally (not gated behind
+/// `should_consume_input`, unlike `default_input_map`) precisely so that a UI temporarily
+/// disabling input actively releases the cursor instead of leaving it locked underneath it.
+pub fn grab_cursor_system(
+    mut windows: ResMut<Windows>,
+    mut released: ResMut<CursorReleasedByUser>,
+    input_behavior: Res<InputBehavior>,
+    keyboard: Res<Input<KeyCode>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    cameras: Query<&Fps3dCameraController>,
+) {
+    let window = match windows.get_primary_mut() {
+        Some(window) => window,
+        None => return,
+    };
+
+    if keyboard.just_pressed(KeyCode::Escape) {
+        released.0 = true;
+    } else if mouse_buttons.just_pressed(MouseButton::Left) {
+        released.0 = false;
+    }
+
+    let should_grab = !released.0
+        && *input_behavior == InputBehavior::Enable
+        && cameras.iter().any(|controller| controller.enabled);
+    window.set_cursor_lock_mode(should_grab);
+    window.set_cursor_visibility(!should_grab);
+}
@@ -0,0 +1,376 @@
+use super::{set_default_input_behavior, should_consume_input, ActiveLookCamera, Binding};
+use crate::look_transform64::FloatingOrigin;
+
+use bevy::{
+    app::prelude::*,
+    ecs::{bundle::Bundle, prelude::*},
+    input::{mouse::MouseMotion, prelude::*},
+    math::{prelude::*, DVec3},
+    render::prelude::*,
+    transform::components::Transform,
+};
+use serde::{Deserialize, Serialize};
+
+/// A 6-DOF free-flight camera: yaw/pitch/roll accumulate directly as a quaternion in
+/// `FreeFlyTransform` (smoothed into the camera `Transform`), rather than via `LookAngles`,
+/// so it can pitch past vertical and roll -
+/// essential for flight/space cameras, and something `fps`/`orbit` intentionally forbid via
+/// `LookAngles::assert_not_looking_up`.
+#[derive(Default)]
+pub struct FreeFlyCameraPlugin {
+    pub override_input_system: bool,
+}
+
+impl FreeFlyCameraPlugin {
+    pub fn new(override_input_system: bool) -> Self {
+        Self {
+            override_input_system,
+        }
+    }
+}
+
+impl Plugin for FreeFlyCameraPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        let app = app
+            .add_startup_system(set_default_input_behavior.system())
+            .add_system(control_system.system())
+            .add_system(smoothing_system.system())
+            .add_event::<ControlEvent>();
+        if !self.override_input_system {
+            app.add_system_set(
+                SystemSet::new()
+                    .with_run_criteria(should_consume_input.system())
+                    .with_system(default_input_map.system()),
+            );
+        }
+    }
+}
+
+#[derive(Bundle)]
+pub struct FreeFlyCameraBundle {
+    controller: FreeFlyCameraController,
+    bindings: FreeFlyControlBindings,
+    raw_transform: FreeFlyTransform,
+    smoother: FreeFlySmoother,
+    #[bundle]
+    perspective: PerspectiveCameraBundle,
+}
+
+impl FreeFlyCameraBundle {
+    pub fn new(
+        controller: FreeFlyCameraController,
+        mut perspective: PerspectiveCameraBundle,
+        translation: Vec3,
+        rotation: Quat,
+    ) -> Self {
+        // Make sure the transform is consistent with the controller to start.
+        perspective.transform = Transform {
+            translation,
+            rotation,
+            ..Default::default()
+        };
+
+        Self {
+            controller,
+            bindings: FreeFlyControlBindings::default(),
+            raw_transform: FreeFlyTransform {
+                translation,
+                rotation,
+            },
+            smoother: FreeFlySmoother::new(translation, rotation, controller.smoothing_weight),
+            perspective,
+        }
+    }
+
+    pub fn with_bindings(mut self, bindings: FreeFlyControlBindings) -> Self {
+        self.bindings = bindings;
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct FreeFlyCameraController {
+    pub enabled: bool,
+    pub mouse_rotate_sensitivity: Vec2,
+    pub roll_sensitivity: f32,
+    pub translate_sensitivity: f32,
+    pub smoothing_weight: f32,
+}
+
+impl Default for FreeFlyCameraController {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            mouse_rotate_sensitivity: Vec2::splat(0.002),
+            roll_sensitivity: 1.5,
+            translate_sensitivity: 0.5,
+            smoothing_weight: 0.9,
+        }
+    }
+}
+
+/// Rebindable keys for `FreeFlyCameraController`'s actions.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct FreeFlyControlBindings {
+    pub key_forward: KeyCode,
+    pub key_back: KeyCode,
+    pub key_left: KeyCode,
+    pub key_right: KeyCode,
+    pub key_up: KeyCode,
+    pub key_down: KeyCode,
+    pub key_roll_left: KeyCode,
+    pub key_roll_right: KeyCode,
+}
+
+impl Default for FreeFlyControlBindings {
+    fn default() -> Self {
+        Self {
+            key_forward: KeyCode::W,
+            key_back: KeyCode::S,
+            key_left: KeyCode::A,
+            key_right: KeyCode::D,
+            key_up: KeyCode::E,
+            key_down: KeyCode::Q,
+            key_roll_left: KeyCode::Z,
+            key_roll_right: KeyCode::X,
+        }
+    }
+}
+
+/// The raw, unsmoothed state `control_system` accumulates events into each frame. A separate
+/// `FreeFlySmoother` lerps/slerps toward this, and its result is what's actually written into
+/// `Transform`, the same split `LookTransform`/`Smoother` use for the other controllers.
+#[derive(Clone, Copy, Debug)]
+pub struct FreeFlyTransform {
+    pub translation: Vec3,
+    pub rotation: Quat,
+}
+
+/// Smooths the real `Transform` toward `FreeFlyTransform`, using the same exponential
+/// smoothing scheme as `Smoother`/`FovSmoother` (lerp for translation, slerp for rotation).
+#[derive(Clone, Copy, Debug)]
+pub struct FreeFlySmoother {
+    translation: Vec3,
+    rotation: Quat,
+    pub smoothing_weight: f32,
+}
+
+impl FreeFlySmoother {
+    pub fn new(translation: Vec3, rotation: Quat, smoothing_weight: f32) -> Self {
+        Self {
+            translation,
+            rotation,
+            smoothing_weight,
+        }
+    }
+}
+
+pub enum ControlEvent {
+    Rotate(Vec2),
+    Roll(f32),
+    Translate(Vec3),
+}
+
+pub fn default_input_map(
+    mut events: EventWriter<ControlEvent>,
+    keyboard: Res<Input<KeyCode>>,
+    mut mouse_motion_events: EventReader<MouseMotion>,
+    active_cameras: Query<
+        (&FreeFlyCameraController, &FreeFlyControlBindings),
+        With<ActiveLookCamera>,
+    >,
+    all_cameras: Query<(&FreeFlyCameraController, &FreeFlyControlBindings)>,
+) {
+    // Prefer the camera marked active; if none is marked (e.g. a single-camera scene),
+    // fall back to the first one found.
+    let (controller, bindings) = if let Some(c) = active_cameras.iter().next() {
+        c
+    } else if let Some(c) = all_cameras.iter().next() {
+        c
+    } else {
+        return;
+    };
+
+    let FreeFlyCameraController {
+        enabled,
+        translate_sensitivity,
+        mouse_rotate_sensitivity,
+        roll_sensitivity,
+        ..
+    } = *controller;
+
+    if !enabled {
+        return;
+    }
+
+    let mut cursor_delta = Vec2::ZERO;
+    for event in mouse_motion_events.iter() {
+        cursor_delta += event.delta;
+    }
+
+    events.send(ControlEvent::Rotate(
+        mouse_rotate_sensitivity * cursor_delta,
+    ));
+
+    if keyboard.pressed(bindings.key_roll_left) {
+        events.send(ControlEvent::Roll(roll_sensitivity));
+    }
+    if keyboard.pressed(bindings.key_roll_right) {
+        events.send(ControlEvent::Roll(-roll_sensitivity));
+    }
+
+    for (key, dir) in [
+        (bindings.key_forward, -Vec3::Z),
+        (bindings.key_left, -Vec3::X),
+        (bindings.key_back, Vec3::Z),
+        (bindings.key_right, Vec3::X),
+        (bindings.key_up, Vec3::Y),
+        (bindings.key_down, -Vec3::Y),
+    ]
+    .iter()
+    .cloned()
+    {
+        if keyboard.pressed(key) {
+            events.send(ControlEvent::Translate(translate_sensitivity * dir));
+        }
+    }
+}
+
+pub fn control_system(
+    mut events: EventReader<ControlEvent>,
+    active: Query<Entity, (With<FreeFlyCameraController>, With<ActiveLookCamera>)>,
+    any: Query<Entity, With<FreeFlyCameraController>>,
+    mut cameras: Query<(&FreeFlyCameraController, &mut FreeFlyTransform)>,
+) {
+    // Prefer the camera marked active; if none is marked (e.g. a single-camera scene),
+    // fall back to the first one found.
+    let target = active.iter().next().or_else(|| any.iter().next());
+    let (controller, mut raw_transform) = match target.and_then(|e| cameras.get_mut(e).ok()) {
+        Some(c) => c,
+        None => return,
+    };
+
+    if !controller.enabled {
+        events.iter(); // Drop the events.
+        return;
+    }
+
+    for event in events.iter() {
+        match event {
+            ControlEvent::Rotate(delta) => {
+                // Applied directly to the accumulated quaternion rather than through
+                // `LookAngles`, so pitch can go past vertical (no assert_not_looking_up).
+                // Composed in local space, like `Roll`, so yaw stays consistent with
+                // pitch/roll once the camera has rolled off its back.
+                let yaw = Quat::from_rotation_y(-delta.x);
+                let pitch = Quat::from_rotation_x(-delta.y);
+                raw_transform.rotation = raw_transform.rotation * yaw * pitch;
+            }
+            ControlEvent::Roll(delta) => {
+                raw_transform.rotation = raw_transform.rotation * Quat::from_rotation_z(*delta);
+            }
+            ControlEvent::Translate(delta) => {
+                raw_transform.translation += raw_transform.rotation * *delta;
+            }
+        }
+    }
+}
+
+pub fn smoothing_system(
+    mut cameras: Query<(&FreeFlyTransform, &mut FreeFlySmoother, &mut Transform)>,
+) {
+    for (raw_transform, mut smoother, mut transform) in cameras.iter_mut() {
+        let lerp_factor = 1.0 - smoother.smoothing_weight;
+        smoother.translation = smoother
+            .translation
+            .lerp(raw_transform.translation, lerp_factor);
+        smoother.rotation = smoother.rotation.slerp(raw_transform.rotation, lerp_factor);
+
+        transform.translation = smoother.translation;
+        transform.rotation = smoother.rotation;
+    }
+}
+
+/// Double-precision analogue of `FreeFlyTransform`, accumulated by `control_system_f64` for
+/// scenes that rebase around a `FloatingOrigin` instead of trusting an f32 translation.
+#[derive(Clone, Copy, Debug)]
+pub struct FreeFlyTransform64 {
+    pub translation: DVec3,
+    pub rotation: Quat,
+}
+
+/// Double-precision analogue of `FreeFlySmoother`.
+#[derive(Clone, Copy, Debug)]
+pub struct FreeFlySmoother64 {
+    translation: DVec3,
+    rotation: Quat,
+    pub smoothing_weight: f32,
+}
+
+impl FreeFlySmoother64 {
+    pub fn new(translation: DVec3, rotation: Quat, smoothing_weight: f32) -> Self {
+        Self {
+            translation,
+            rotation,
+            smoothing_weight,
+        }
+    }
+}
+
+/// Double-precision counterpart to `control_system`, accumulating into `FreeFlyTransform64`
+/// instead of the f32 `FreeFlyTransform`.
+pub fn control_system_f64(
+    mut events: EventReader<ControlEvent>,
+    active: Query<Entity, (With<FreeFlyCameraController>, With<ActiveLookCamera>)>,
+    any: Query<Entity, With<FreeFlyCameraController>>,
+    mut cameras: Query<(&FreeFlyCameraController, &mut FreeFlyTransform64)>,
+) {
+    // Prefer the camera marked active; if none is marked (e.g. a single-camera scene),
+    // fall back to the first one found.
+    let target = active.iter().next().or_else(|| any.iter().next());
+    let (controller, mut raw_transform) = match target.and_then(|e| cameras.get_mut(e).ok()) {
+        Some(c) => c,
+        None => return,
+    };
+
+    if !controller.enabled {
+        events.iter(); // Drop the events.
+        return;
+    }
+
+    for event in events.iter() {
+        match event {
+            ControlEvent::Rotate(delta) => {
+                // Composed in local space, like `Roll`, so yaw stays consistent with
+                // pitch/roll once the camera has rolled off its back.
+                let yaw = Quat::from_rotation_y(-delta.x);
+                let pitch = Quat::from_rotation_x(-delta.y);
+                raw_transform.rotation = raw_transform.rotation * yaw * pitch;
+            }
+            ControlEvent::Roll(delta) => {
+                raw_transform.rotation = raw_transform.rotation * Quat::from_rotation_z(*delta);
+            }
+            ControlEvent::Translate(delta) => {
+                raw_transform.translation += (raw_transform.rotation * *delta).as_dvec3();
+            }
+        }
+    }
+}
+
+/// Double-precision counterpart to `smoothing_system`, lerping `FreeFlyTransform64` into a
+/// `FreeFlySmoother64` and rebasing the result around `FloatingOrigin` when writing `Transform`.
+pub fn smoothing_system_f64(
+    origin: Res<FloatingOrigin>,
+    mut cameras: Query<(&FreeFlyTransform64, &mut FreeFlySmoother64, &mut Transform)>,
+) {
+    for (raw_transform, mut smoother, mut transform) in cameras.iter_mut() {
+        let lerp_factor = 1.0 - smoother.smoothing_weight;
+        smoother.translation = smoother
+            .translation
+            .lerp(raw_transform.translation, lerp_factor as f64);
+        smoother.rotation = smoother.rotation.slerp(raw_transform.rotation, lerp_factor);
+
+        transform.translation = (smoother.translation - origin.0).as_vec3();
+        transform.rotation = smoother.rotation;
+    }
+}
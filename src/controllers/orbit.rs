@@ -1,5 +1,6 @@
 use crate::{LookAngles, LookTransform, LookTransformBundle, Smoother};
-use super::{set_default_input_behavior, should_consume_input};
+use crate::look_transform64::{LookAngles64, LookTransform64};
+use super::{set_default_input_behavior, should_consume_input, ActiveLookCamera, Binding};
 
 use bevy::{
     app::prelude::*,
@@ -20,6 +21,7 @@ impl Plugin for OrbitCameraPlugin {
     fn build(&self, app: &mut AppBuilder) {
         app.add_startup_system(set_default_input_behavior.system())
             .add_system(control_system.system())
+            .add_system(fov_smoothing_system.system())
             .add_event::<ControlEvent>()
             .add_system_set(
                 SystemSet::new()
@@ -32,6 +34,8 @@ impl Plugin for OrbitCameraPlugin {
 #[derive(Bundle)]
 pub struct OrbitCameraBundle {
     controller: OrbitCameraController,
+    bindings: OrbitControlBindings,
+    fov_smoother: FovSmoother,
     #[bundle]
     look_transform: LookTransformBundle,
     #[bundle]
@@ -50,6 +54,11 @@ impl OrbitCameraBundle {
 
         Self {
             controller,
+            bindings: OrbitControlBindings::default(),
+            fov_smoother: FovSmoother::new(
+                perspective.perspective_projection.fov,
+                controller.smoothing_weight,
+            ),
             look_transform: LookTransformBundle {
                 transform: LookTransform { eye, target },
                 smoother: Smoother::new(controller.smoothing_weight),
@@ -57,6 +66,18 @@ impl OrbitCameraBundle {
             perspective,
         }
     }
+
+    pub fn with_bindings(mut self, bindings: OrbitControlBindings) -> Self {
+        self.bindings = bindings;
+        self
+    }
+}
+
+/// Whether the mouse wheel scales the orbit radius or drives a smoothed FOV change instead.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum ZoomMode {
+    Radius,
+    Fov,
 }
 
 /// A 3rd person camera that orbits around the target.
@@ -67,6 +88,11 @@ pub struct OrbitCameraController {
     pub mouse_translate_sensitivity: Vec2,
     pub mouse_wheel_zoom_sensitivity: f32,
     pub smoothing_weight: f32,
+    pub zoom_mode: ZoomMode,
+    /// Radians/notch applied to the target FOV in [`ZoomMode::Fov`].
+    pub fov_zoom_sensitivity: f32,
+    pub min_fov: f32,
+    pub max_fov: f32,
 }
 
 impl Default for OrbitCameraController {
@@ -77,6 +103,44 @@ impl Default for OrbitCameraController {
             mouse_wheel_zoom_sensitivity: 0.15,
             smoothing_weight: 0.8,
             enabled: true,
+            zoom_mode: ZoomMode::Radius,
+            fov_zoom_sensitivity: 0.05,
+            min_fov: 0.1,
+            max_fov: 1.5,
+        }
+    }
+}
+
+/// Smooths FOV changes toward a target, writing the result into `PerspectiveProjection`.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct FovSmoother {
+    pub current_fov: f32,
+    pub target_fov: f32,
+    pub smoothing_weight: f32,
+}
+
+impl FovSmoother {
+    pub fn new(fov: f32, smoothing_weight: f32) -> Self {
+        Self {
+            current_fov: fov,
+            target_fov: fov,
+            smoothing_weight,
+        }
+    }
+}
+
+/// Rebindable keys/buttons for `OrbitCameraController`'s actions.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct OrbitControlBindings {
+    pub orbit_modifier: Binding,
+    pub pan_modifier: Binding,
+}
+
+impl Default for OrbitControlBindings {
+    fn default() -> Self {
+        Self {
+            orbit_modifier: Binding::Key(KeyCode::LControl),
+            pan_modifier: Binding::MouseButton(MouseButton::Right),
         }
     }
 }
@@ -85,6 +149,7 @@ pub enum ControlEvent {
     Orbit(Vec2),
     TranslateTarget(Vec2),
     Zoom(f32),
+    ZoomFov(f32),
 }
 
 pub struct DisableDefaultInput;
@@ -95,11 +160,15 @@ pub fn default_input_map(
     mut mouse_motion_events: EventReader<MouseMotion>,
     mouse_buttons: Res<Input<MouseButton>>,
     keyboard: Res<Input<KeyCode>>,
-    controllers: Query<&OrbitCameraController>,
+    active_controllers: Query<(&OrbitCameraController, &OrbitControlBindings), With<ActiveLookCamera>>,
+    all_controllers: Query<(&OrbitCameraController, &OrbitControlBindings)>,
 ) {
-    // Can only control one camera at a time.
-    let controller = if let Some(controller) = controllers.iter().next() {
-        controller
+    // Prefer the camera marked active; if none is marked (e.g. a single-camera scene),
+    // fall back to the first one found.
+    let (controller, bindings) = if let Some(c) = active_controllers.iter().next() {
+        c
+    } else if let Some(c) = all_controllers.iter().next() {
+        c
     } else {
         return;
     };
@@ -108,6 +177,8 @@ pub fn default_input_map(
         mouse_rotate_sensitivity,
         mouse_translate_sensitivity,
         mouse_wheel_zoom_sensitivity,
+        zoom_mode,
+        fov_zoom_sensitivity,
         ..
     } = *controller;
 
@@ -120,33 +191,52 @@ pub fn default_input_map(
         cursor_delta += event.delta;
     }
 
-    if keyboard.pressed(KeyCode::LControl) {
+    if bindings.orbit_modifier.pressed(&keyboard, &mouse_buttons) {
         events.send(ControlEvent::Orbit(mouse_rotate_sensitivity * cursor_delta));
     }
 
-    if mouse_buttons.pressed(MouseButton::Right) {
+    if bindings.pan_modifier.pressed(&keyboard, &mouse_buttons) {
         events.send(ControlEvent::TranslateTarget(
             mouse_translate_sensitivity * cursor_delta,
         ));
     }
 
-    let mut scalar = 1.0;
-    for event in mouse_wheel_reader.iter() {
-        scalar *= 1.0 + event.y * mouse_wheel_zoom_sensitivity;
+    match zoom_mode {
+        ZoomMode::Radius => {
+            let mut scalar = 1.0;
+            for event in mouse_wheel_reader.iter() {
+                scalar *= 1.0 + event.y * mouse_wheel_zoom_sensitivity;
+            }
+            events.send(ControlEvent::Zoom(scalar));
+        }
+        ZoomMode::Fov => {
+            let mut delta = 0.0;
+            for event in mouse_wheel_reader.iter() {
+                delta -= event.y * fov_zoom_sensitivity;
+            }
+            events.send(ControlEvent::ZoomFov(delta));
+        }
     }
-    events.send(ControlEvent::Zoom(scalar));
 }
 
 pub fn control_system(
     mut events: EventReader<ControlEvent>,
-    mut cameras: Query<(&OrbitCameraController, &mut LookTransform, &Transform)>,
+    active: Query<Entity, (With<OrbitCameraController>, With<ActiveLookCamera>)>,
+    any: Query<Entity, With<OrbitCameraController>>,
+    mut cameras: Query<(
+        &OrbitCameraController,
+        &mut LookTransform,
+        &Transform,
+        &mut FovSmoother,
+    )>,
 ) {
-    // Can only control one camera at a time.
-    let (controller, mut transform, scene_transform) =
-        if let Some((controller, transform, scene_transform)) = cameras.iter_mut().next() {
-            (controller, transform, scene_transform)
-        } else {
-            return;
+    // Prefer the camera marked active; if none is marked (e.g. a single-camera scene),
+    // fall back to the first one found.
+    let target = active.iter().next().or_else(|| any.iter().next());
+    let (controller, mut transform, scene_transform, mut fov_smoother) =
+        match target.and_then(|e| cameras.get_mut(e).ok()) {
+            Some(c) => c,
+            None => return,
         };
 
     if controller.enabled {
@@ -167,6 +257,10 @@ pub fn control_system(
                 ControlEvent::Zoom(scalar) => {
                     radius_scalar *= scalar;
                 }
+                ControlEvent::ZoomFov(delta) => {
+                    fov_smoother.target_fov = (fov_smoother.target_fov + delta)
+                        .clamp(controller.min_fov, controller.max_fov);
+                }
             }
         }
 
@@ -178,3 +272,74 @@ pub fn control_system(
         events.iter(); // Drop the events.
     }
 }
+
+/// Double-precision counterpart to `control_system`, operating on `LookTransform64` for
+/// scenes that rebase around a `FloatingOrigin` instead of trusting an f32 `Transform`.
+pub fn control_system_f64(
+    mut events: EventReader<ControlEvent>,
+    active: Query<Entity, (With<OrbitCameraController>, With<ActiveLookCamera>)>,
+    any: Query<Entity, With<OrbitCameraController>>,
+    mut cameras: Query<(
+        &OrbitCameraController,
+        &mut LookTransform64,
+        &Transform,
+        &mut FovSmoother,
+    )>,
+) {
+    // Prefer the camera marked active; if none is marked (e.g. a single-camera scene),
+    // fall back to the first one found.
+    let target = active.iter().next().or_else(|| any.iter().next());
+    let (controller, mut transform, scene_transform, mut fov_smoother) =
+        match target.and_then(|e| cameras.get_mut(e).ok()) {
+            Some(c) => c,
+            None => return,
+        };
+
+    if controller.enabled {
+        let mut look_angles = LookAngles64::from_vector(-transform.look_direction());
+        let mut radius_scalar = 1.0;
+
+        for event in events.iter() {
+            match event {
+                ControlEvent::Orbit(delta) => {
+                    look_angles.add_yaw(-delta.x as f64);
+                    look_angles.add_pitch(delta.y as f64);
+                }
+                ControlEvent::TranslateTarget(delta) => {
+                    let right_dir = (scene_transform.rotation * -Vec3::X).as_dvec3();
+                    let up_dir = (scene_transform.rotation * Vec3::Y).as_dvec3();
+                    transform.target += delta.x as f64 * right_dir + delta.y as f64 * up_dir;
+                }
+                ControlEvent::Zoom(scalar) => {
+                    radius_scalar *= *scalar as f64;
+                }
+                ControlEvent::ZoomFov(delta) => {
+                    fov_smoother.target_fov = (fov_smoother.target_fov + delta)
+                        .clamp(controller.min_fov, controller.max_fov);
+                }
+            }
+        }
+
+        look_angles.assert_not_looking_up();
+
+        transform.eye =
+            transform.target + radius_scalar * transform.radius() * look_angles.unit_vector();
+    } else {
+        events.iter(); // Drop the events.
+    }
+}
+
+/// Lerps each [`FovSmoother`]'s current fov toward its target (exponential smoothing, the
+/// same scheme as `Smoother`) and writes it into the entity's `PerspectiveProjection`.
+/// Has no visible effect while `OrbitCameraController::zoom_mode` is `ZoomMode::Radius`,
+/// since nothing ever changes `target_fov` in that mode.
+pub fn fov_smoothing_system(
+    mut cameras: Query<(&mut FovSmoother, &mut PerspectiveProjection)>,
+) {
+    for (mut fov_smoother, mut projection) in cameras.iter_mut() {
+        fov_smoother.current_fov = fov_smoother.current_fov
+            + (1.0 - fov_smoother.smoothing_weight)
+                * (fov_smoother.target_fov - fov_smoother.current_fov);
+        projection.fov = fov_smoother.current_fov;
+    }
+}
@@ -0,0 +1,131 @@
+//! Double-precision counterpart to `LookTransform`, for scenes (space/planetary scale) where
+//! an f32 `Vec3` eye/target starts to jitter far from the origin.
+//!
+//! Controllers that need this precision write into `LookTransform64` through an `*_f64`
+//! variant of their `control_system` (see `controllers::orbit::control_system_f64` and
+//! `controllers::fps_3d::control_system_f64`) instead of the usual f32 `control_system`;
+//! `controllers::freefly::control_system_f64` accumulates into its own `FreeFlyTransform64`
+//! instead. `smoothing_system` lerps `LookTransform64` into `Smoother64`, and
+//! `floating_origin_system` then rebases the smoothed `DVec3` position around a
+//! `FloatingOrigin` each frame and writes the result into the entity's ordinary f32 `Transform`.
+
+use crate::controllers::ActiveLookCamera;
+
+use bevy::{
+    ecs::prelude::*,
+    math::DVec3,
+    transform::components::Transform,
+};
+use serde::{Deserialize, Serialize};
+
+/// Double-precision analogue of `LookTransform`.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct LookTransform64 {
+    pub eye: DVec3,
+    pub target: DVec3,
+}
+
+impl LookTransform64 {
+    pub fn look_direction(&self) -> DVec3 {
+        (self.target - self.eye).normalize()
+    }
+
+    pub fn radius(&self) -> f64 {
+        (self.target - self.eye).length()
+    }
+}
+
+/// Double-precision analogue of `Smoother`. An f64 `control_system` writes the raw,
+/// unsmoothed value into `LookTransform64`; `smoothing_system` lerps it into here, and
+/// `floating_origin_system` reads the result.
+#[derive(Clone, Copy, Debug)]
+pub struct Smoother64 {
+    pub eye: DVec3,
+    pub target: DVec3,
+    pub smoothing_weight: f64,
+}
+
+impl Smoother64 {
+    pub fn new(eye: DVec3, target: DVec3, smoothing_weight: f64) -> Self {
+        Self {
+            eye,
+            target,
+            smoothing_weight,
+        }
+    }
+}
+
+/// Lerps each `Smoother64` toward its entity's raw `LookTransform64`, the f64 analogue of
+/// the exponential smoothing `Smoother` does for `LookTransform`.
+pub fn smoothing_system(mut cameras: Query<(&LookTransform64, &mut Smoother64)>) {
+    for (raw, mut smoother) in cameras.iter_mut() {
+        let lerp_factor = 1.0 - smoother.smoothing_weight;
+        smoother.eye = smoother.eye.lerp(raw.eye, lerp_factor);
+        smoother.target = smoother.target.lerp(raw.target, lerp_factor);
+    }
+}
+
+/// Double-precision analogue of `LookAngles`, used by the f64 `control_system` variants.
+pub struct LookAngles64 {
+    yaw: f64,
+    pitch: f64,
+}
+
+impl LookAngles64 {
+    pub fn from_vector(vector: DVec3) -> Self {
+        Self {
+            yaw: vector.z.atan2(vector.x),
+            pitch: vector.y.atan2((vector.x * vector.x + vector.z * vector.z).sqrt()),
+        }
+    }
+
+    pub fn add_yaw(&mut self, delta: f64) {
+        self.yaw += delta;
+    }
+
+    pub fn add_pitch(&mut self, delta: f64) {
+        self.pitch += delta;
+    }
+
+    pub fn assert_not_looking_up(&self) {
+        let up_vector_tolerance = 0.001;
+        assert!(
+            self.pitch.abs() < std::f64::consts::FRAC_PI_2 - up_vector_tolerance,
+            "Looking straight up/down breaks camera yaw"
+        );
+    }
+
+    pub fn unit_vector(&self) -> DVec3 {
+        DVec3::new(
+            self.yaw.cos() * self.pitch.cos(),
+            self.pitch.sin(),
+            self.yaw.sin() * self.pitch.cos(),
+        )
+    }
+}
+
+/// The point in `LookTransform64` space that the f32 `Transform` tree is currently rebased
+/// around. World geometry should be shifted by `-origin` (or kept in its own f64 space and
+/// rebased the same way) to stay consistent with rebased cameras.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FloatingOrigin(pub DVec3);
+
+/// Each frame, picks the active camera's `Smoother64::eye` (falling back to the first one
+/// found) as the new floating origin, then writes every entity's f32 `Transform` from its
+/// `Smoother64` minus that origin.
+pub fn floating_origin_system(
+    mut origin: ResMut<FloatingOrigin>,
+    active_eyes: Query<&Smoother64, With<ActiveLookCamera>>,
+    any_eyes: Query<&Smoother64>,
+    mut cameras: Query<(&Smoother64, &mut Transform)>,
+) {
+    if let Some(smoother) = active_eyes.iter().next().or_else(|| any_eyes.iter().next()) {
+        origin.0 = smoother.eye;
+    }
+
+    for (smoother, mut transform) in cameras.iter_mut() {
+        let eye = (smoother.eye - origin.0).as_vec3();
+        let target = (smoother.target - origin.0).as_vec3();
+        *transform = Transform::from_translation(eye).looking_at(target, bevy::math::Vec3::Y);
+    }
+}